@@ -0,0 +1,139 @@
+// Copyright 2018 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Early, info-based admission control for incoming connections and joins.
+//!
+//! `transport::ConnectionLimits` admits or refuses purely on counts. This module lets a
+//! caller-supplied [`AdmissionPolicy`] decide from a candidate's [`CandidateInfo`] - its
+//! `PublicId`, claimed `SrcLocation`, and remote `SocketAddr` - before a connection is fully
+//! established or a node is relocated into a section. It is paired with an [`AllowBlockList`]
+//! keyed on `XorName` so known-bad or explicitly-permitted peers can be filtered at the edge
+//! without consulting the policy at all.
+//!
+//! This module only provides the types and the [`admit`] decision. `NodeConfig` has no field
+//! for a policy or a list, and neither `Comm` nor the elders' join handling calls [`admit`], so
+//! no inbound connection or join is filtered by it.
+
+use crate::{id::PublicId, location::SrcLocation};
+use std::{collections::BTreeSet, net::SocketAddr, sync::Arc};
+use xor_name::XorName;
+
+/// Information available about a candidate before its connection is fully established or it is
+/// relocated into a section.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CandidateInfo {
+    /// The candidate's claimed identity.
+    pub public_id: PublicId,
+    /// The location the candidate claims to be joining or sending from.
+    pub claimed_src: SrcLocation,
+    /// The remote address the candidate connected, or is attempting to connect, from.
+    pub addr: SocketAddr,
+}
+
+/// Decision returned by an [`AdmissionPolicy`] or found in an [`AllowBlockList`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Decision {
+    /// Allow the candidate to proceed.
+    Accept,
+    /// Refuse the candidate.
+    Reject,
+}
+
+/// A caller-supplied callback consulted before admitting an inbound connection or a join.
+pub type AdmissionPolicy = Arc<dyn Fn(&CandidateInfo) -> Decision + Send + Sync>;
+
+/// A static allow/block list keyed on `XorName`, consulted ahead of an [`AdmissionPolicy`].
+///
+/// A name on the block list is always rejected and a name on the allow list is always accepted,
+/// regardless of what the policy would otherwise decide; this lets an operator hard-code
+/// known-bad or known-good peers without relying on the policy callback at all.
+#[derive(Clone, Default, Debug)]
+pub struct AllowBlockList {
+    allowed: BTreeSet<XorName>,
+    blocked: BTreeSet<XorName>,
+}
+
+impl AllowBlockList {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `name` to the allow list, removing it from the block list if present.
+    pub fn allow(&mut self, name: XorName) {
+        let _ = self.blocked.remove(&name);
+        let _ = self.allowed.insert(name);
+    }
+
+    /// Adds `name` to the block list, removing it from the allow list if present.
+    pub fn block(&mut self, name: XorName) {
+        let _ = self.allowed.remove(&name);
+        let _ = self.blocked.insert(name);
+    }
+
+    /// Looks `name` up, returning `None` if it is on neither list - in which case the caller
+    /// should fall back to its [`AdmissionPolicy`].
+    pub fn check(&self, name: &XorName) -> Option<Decision> {
+        if self.blocked.contains(name) {
+            Some(Decision::Reject)
+        } else if self.allowed.contains(name) {
+            Some(Decision::Accept)
+        } else {
+            None
+        }
+    }
+}
+
+/// Decides whether to admit `candidate`, consulting `list` first and falling back to `policy`
+/// only when the list has no opinion about `candidate`'s name.
+pub fn admit(candidate: &CandidateInfo, list: &AllowBlockList, policy: &AdmissionPolicy) -> Decision {
+    list.check(candidate.public_id.name())
+        .unwrap_or_else(|| policy(candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate() -> CandidateInfo {
+        CandidateInfo {
+            public_id: PublicId::random(),
+            claimed_src: SrcLocation::EndUser(XorName::random()),
+            addr: "127.0.0.1:12000".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn blocked_name_is_rejected_regardless_of_policy() {
+        let candidate = candidate();
+        let mut list = AllowBlockList::new();
+        list.block(*candidate.public_id.name());
+        let accept_all: AdmissionPolicy = Arc::new(|_| Decision::Accept);
+
+        assert_eq!(admit(&candidate, &list, &accept_all), Decision::Reject);
+    }
+
+    #[test]
+    fn allowed_name_is_accepted_regardless_of_policy() {
+        let candidate = candidate();
+        let mut list = AllowBlockList::new();
+        list.allow(*candidate.public_id.name());
+        let reject_all: AdmissionPolicy = Arc::new(|_| Decision::Reject);
+
+        assert_eq!(admit(&candidate, &list, &reject_all), Decision::Accept);
+    }
+
+    #[test]
+    fn unlisted_name_defers_to_policy() {
+        let candidate = candidate();
+        let list = AllowBlockList::new();
+        let reject_all: AdmissionPolicy = Arc::new(|_| Decision::Reject);
+
+        assert_eq!(admit(&candidate, &list, &reject_all), Decision::Reject);
+    }
+}
@@ -78,12 +78,16 @@ extern crate serde;
 // Public API
 // ############################################################################
 pub use self::{
+    admission::{AdmissionPolicy, AllowBlockList, CandidateInfo, Decision},
     error::{Error, Result},
     id::{FullId, P2pNode, PublicId},
     location::{DstLocation, SrcLocation},
+    membership_mode::MembershipMode,
     network_params::NetworkParams,
     node::{EventStream, Node, NodeConfig},
+    rendezvous::Namespace,
     section::{SectionProofChain, MIN_AGE},
+    transport::ConnectionLimits,
 };
 pub use qp2p::Config as TransportConfig;
 
@@ -94,6 +98,10 @@ pub mod log_ident;
 /// Random number generation
 pub mod rng;
 
+/// Optional open-metrics/Prometheus instrumentation.
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
 // ############################################################################
 // Mock and test API
 // ############################################################################
@@ -120,20 +128,24 @@ pub mod test_consts {
 // Private
 // ############################################################################
 
+mod admission;
 mod cancellation;
 mod consensus;
 mod delivery_group;
 mod error;
 mod id;
 mod location;
+mod membership_mode;
 mod message_filter;
 mod messages;
 mod network_params;
 mod node;
 mod relocation;
+mod rendezvous;
 mod section;
 mod time;
 mod timer;
+mod transport;
 
 // Cryptography
 mod crypto;
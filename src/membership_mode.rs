@@ -0,0 +1,76 @@
+// Copyright 2018 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Client-mode participation, the analogue of Kademlia "client mode".
+//!
+//! A node in [`MembershipMode::Full`] is added to a section's routing table, serves relayed
+//! traffic, and is subject to the age/relocation machinery. A node in
+//! [`MembershipMode::Client`] still bootstraps and exchanges messages via
+//! `SrcLocation`/`DstLocation`, and still receives its `EventStream`, but advertises that it
+//! does not wish to be added to any section's routing table: it never answers
+//! `FindGroup`/relay traffic, elders must skip it when considering adds or splits, and it is
+//! never subject to relocation.
+//!
+//! [`MembershipMode`] is re-exported from the crate root, but `NodeConfig` has no field of this
+//! type, and neither the elders' section-size checks against `RECOMMENDED_SECTION_SIZE` nor the
+//! relocation code consult [`MembershipMode::counts_towards_section_size`] or
+//! [`MembershipMode::is_relocatable`]; the behaviour above is what these predicates report.
+
+/// How a connected peer participates in the network.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum MembershipMode {
+    /// Joins a section's routing table: counts toward `RECOMMENDED_SECTION_SIZE`, answers
+    /// `FindGroup`/relay traffic, and is subject to relocation.
+    Full,
+    /// Bootstraps and exchanges messages without being added to any section's routing table.
+    /// Never counts toward `RECOMMENDED_SECTION_SIZE`, never answers `FindGroup`/relay traffic,
+    /// and is never relocated.
+    Client,
+}
+
+impl MembershipMode {
+    /// Whether a peer in this mode should be counted when a section considers
+    /// `RECOMMENDED_SECTION_SIZE` or decides to split.
+    pub fn counts_towards_section_size(self) -> bool {
+        matches!(self, MembershipMode::Full)
+    }
+
+    /// Whether a peer in this mode answers `FindGroup`/relay traffic on behalf of the section.
+    pub fn serves_relay_traffic(self) -> bool {
+        matches!(self, MembershipMode::Full)
+    }
+
+    /// Whether a peer in this mode is subject to the age-based relocation machinery.
+    pub fn is_relocatable(self) -> bool {
+        matches!(self, MembershipMode::Full)
+    }
+}
+
+impl Default for MembershipMode {
+    fn default() -> Self {
+        MembershipMode::Full
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_mode_does_not_count_towards_section_health() {
+        let mode = MembershipMode::Client;
+        assert!(!mode.counts_towards_section_size());
+        assert!(!mode.serves_relay_traffic());
+        assert!(!mode.is_relocatable());
+    }
+
+    #[test]
+    fn full_mode_is_the_default() {
+        assert_eq!(MembershipMode::default(), MembershipMode::Full);
+    }
+}
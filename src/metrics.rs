@@ -0,0 +1,162 @@
+// Copyright 2018 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Optional open-metrics/Prometheus instrumentation for routing internals.
+//!
+//! Enabled via the `metrics` feature. [`RoutingMetrics::new`] registers the metric families
+//! against a caller-owned `open_metrics_client::registry::Registry`, and
+//! [`RoutingMetrics::encode`] renders that registry as exposition text for a scraper.
+//! `NodeConfig` has no registry handle and `Node` does not update a [`RoutingMetrics`]; its
+//! values change only when the holder sets its public fields.
+
+use open_metrics_client::encoding::text::encode;
+use open_metrics_client::metrics::counter::Counter;
+use open_metrics_client::metrics::gauge::Gauge;
+use open_metrics_client::registry::Registry;
+
+/// Routing metrics registered against a caller-supplied `Registry`.
+///
+/// Gauges track the quantities this crate already compares against a target
+/// (`RECOMMENDED_SECTION_SIZE`, `ELDER_SIZE`); counters track the quantities that only ever
+/// accumulate.
+pub struct RoutingMetrics {
+    /// Current number of nodes in our section, compare against `RECOMMENDED_SECTION_SIZE`.
+    pub section_size: Gauge,
+    /// Current number of elders in our section, compare against `ELDER_SIZE`.
+    pub elder_count: Gauge,
+    /// Relocations triggered since startup.
+    pub relocations: Counter,
+    /// Messages sent by this node since startup.
+    pub messages_sent: Counter,
+    /// Messages received by this node since startup.
+    pub messages_received: Counter,
+    /// Messages dropped by `message_filter` as duplicates since startup.
+    pub messages_filtered: Counter,
+    /// PARSEC consensus rounds completed since startup.
+    pub consensus_rounds: Counter,
+    /// Section splits observed since startup.
+    pub splits: Counter,
+    /// Section merges observed since startup.
+    pub merges: Counter,
+}
+
+impl RoutingMetrics {
+    /// Creates the metric families and registers them against `registry`.
+    pub fn new(registry: &mut Registry) -> Self {
+        let section_size = Gauge::default();
+        registry.register(
+            "section_size",
+            "Current number of nodes in our section",
+            Box::new(section_size.clone()),
+        );
+
+        let elder_count = Gauge::default();
+        registry.register(
+            "elder_count",
+            "Current number of elders in our section",
+            Box::new(elder_count.clone()),
+        );
+
+        let relocations = Counter::default();
+        registry.register(
+            "relocations_total",
+            "Relocations triggered since startup",
+            Box::new(relocations.clone()),
+        );
+
+        let messages_sent = Counter::default();
+        registry.register(
+            "messages_sent_total",
+            "Messages sent since startup",
+            Box::new(messages_sent.clone()),
+        );
+
+        let messages_received = Counter::default();
+        registry.register(
+            "messages_received_total",
+            "Messages received since startup",
+            Box::new(messages_received.clone()),
+        );
+
+        let messages_filtered = Counter::default();
+        registry.register(
+            "messages_filtered_total",
+            "Messages dropped by message_filter as duplicates since startup",
+            Box::new(messages_filtered.clone()),
+        );
+
+        let consensus_rounds = Counter::default();
+        registry.register(
+            "consensus_rounds_total",
+            "PARSEC consensus rounds completed since startup",
+            Box::new(consensus_rounds.clone()),
+        );
+
+        let splits = Counter::default();
+        registry.register(
+            "splits_total",
+            "Section splits observed since startup",
+            Box::new(splits.clone()),
+        );
+
+        let merges = Counter::default();
+        registry.register(
+            "merges_total",
+            "Section merges observed since startup",
+            Box::new(merges.clone()),
+        );
+
+        Self {
+            section_size,
+            elder_count,
+            relocations,
+            messages_sent,
+            messages_received,
+            messages_filtered,
+            consensus_rounds,
+            splits,
+            merges,
+        }
+    }
+
+    /// Encodes the current state of `registry` as open-metrics text exposition, ready to be
+    /// served to a scraper.
+    pub fn encode(registry: &Registry) -> Result<String, std::fmt::Error> {
+        let mut buffer = String::new();
+        encode(&mut buffer, registry)?;
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_registers_every_family_without_panicking() {
+        let mut registry = Registry::default();
+        let metrics = RoutingMetrics::new(&mut registry);
+        metrics.section_size.set(5);
+        metrics.relocations.inc();
+
+        let encoded = RoutingMetrics::encode(&registry).unwrap();
+        assert!(encoded.contains("section_size"));
+        assert!(encoded.contains("relocations_total"));
+        for name in &[
+            "elder_count",
+            "messages_sent_total",
+            "messages_received_total",
+            "messages_filtered_total",
+            "consensus_rounds_total",
+            "splits_total",
+            "merges_total",
+        ] {
+            assert!(encoded.contains(name), "{} missing from encoding", name);
+        }
+    }
+}
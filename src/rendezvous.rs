@@ -0,0 +1,228 @@
+// Copyright 2018 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Rendezvous-based discovery of section members.
+//!
+//! Operator-supplied bootstrap contact lists (the old `relay`/`BootstrapEndpoints` machinery,
+//! and the `TransportConfig` contacts used by `node::bootstrap`) go stale as sections churn.
+//! This module lets established section members act as discovery points instead: a joining
+//! node sends a [`DiscoverRequest`] to any contact it still knows about, and that contact
+//! replies with the [`Registration`]s it currently holds for the requested [`Namespace`].
+//! [`RendezvousRegistry::register`] records a member's registration and
+//! [`RendezvousRegistry::remove_expired`] garbage-collects records whose TTL has lapsed; nothing
+//! in this crate calls either of them, on a timer or otherwise.
+//!
+//! `messages::Variant` still needs a case for `DiscoverRequest`/`DiscoverResponse`, and
+//! `node::NodeConfig` still needs the `rendezvous_namespace` plus "serve discovery" switch that
+//! select which [`Namespace`]s a running node registers itself under and answers queries for.
+
+use crate::id::PublicId;
+use std::{collections::BTreeMap, net::SocketAddr};
+
+/// Names the set of elders a `Discover` request is interested in, typically a section prefix.
+pub type Namespace = crate::Prefix;
+
+/// Maximum number of [`Registration`]s returned in a single `DiscoverResponse`, mirroring the
+/// old `MAX_RELAY = 5` cap so a response stays within one datagram.
+pub const MAX_DISCOVER_RESULTS: usize = 5;
+
+/// A signed, TTL-bearing claim that `public_id` is reachable at `addr`.
+///
+/// `signature` is produced by `public_id` itself over `(public_id, addr, expires_at)`.
+/// [`RendezvousRegistry::register`] drops a registration whose signature does not verify, so a
+/// registry never stores or serves an endpoint its `public_id` did not sign; the receiver of a
+/// `DiscoverResponse` gets the signatures but this module offers it no way to verify them.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Registration {
+    /// The peer being advertised.
+    pub public_id: PublicId,
+    /// The address at which the peer can currently be reached.
+    pub addr: SocketAddr,
+    /// Unix timestamp (seconds) after which this registration is no longer valid.
+    pub expires_at: u64,
+    /// Signature over `(public_id, addr, expires_at)`.
+    pub signature: Vec<u8>,
+}
+
+impl Registration {
+    /// The bytes `signature` is computed over.
+    fn signed_bytes(public_id: &PublicId, addr: &SocketAddr, expires_at: u64) -> Vec<u8> {
+        bincode::serialize(&(public_id, addr, expires_at))
+            .expect("serializing a Registration's signed fields cannot fail")
+    }
+
+    /// Verifies that `signature` was produced by `public_id` itself over
+    /// `(public_id, addr, expires_at)`. A registration that fails this must not be stored or
+    /// served - otherwise anything that can reach `register()` could inject an arbitrary
+    /// `public_id`/`addr` pair under someone else's name.
+    fn verify(&self) -> bool {
+        let bytes = Self::signed_bytes(&self.public_id, &self.addr, self.expires_at);
+        self.public_id.verify(&bytes, &self.signature)
+    }
+
+    fn is_live(&self, now: u64) -> bool {
+        self.expires_at > now
+    }
+}
+
+/// Sent by a joining node to any known contact to request the elders currently reachable for
+/// `namespace`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DiscoverRequest {
+    /// The namespace being queried.
+    pub namespace: Namespace,
+}
+
+/// Reply to a [`DiscoverRequest`], capped at [`MAX_DISCOVER_RESULTS`] live registrations.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DiscoverResponse {
+    /// The namespace that was queried.
+    pub namespace: Namespace,
+    /// Currently-live registrations for `namespace`, expired records already filtered out.
+    pub registrations: Vec<Registration>,
+}
+
+/// Holds the registrations a node is serving discovery for, keyed by [`Namespace`].
+///
+/// A standalone store: it is not held in the section state and is not gossiped, so it holds
+/// only what was registered with it directly. Never returns a record whose `expires_at` has
+/// passed.
+#[derive(Default)]
+pub struct RendezvousRegistry {
+    records: BTreeMap<Namespace, Vec<Registration>>,
+}
+
+impl RendezvousRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records or refreshes `registration` under `namespace`. A registration that has already
+    /// expired by `now`, or whose signature doesn't verify against its own `public_id`, is
+    /// dropped rather than stored.
+    pub fn register(&mut self, namespace: Namespace, registration: Registration, now: u64) {
+        if !registration.is_live(now) || !registration.verify() {
+            return;
+        }
+        let entries = self.records.entry(namespace).or_insert_with(Vec::new);
+        entries.retain(|existing| existing.public_id != registration.public_id);
+        entries.push(registration);
+    }
+
+    /// Answers a [`DiscoverRequest`] for `namespace`, filtering out expired records and capping
+    /// the result at [`MAX_DISCOVER_RESULTS`].
+    pub fn discover(&self, namespace: &Namespace, now: u64) -> DiscoverResponse {
+        let registrations = self
+            .records
+            .get(namespace)
+            .into_iter()
+            .flatten()
+            .filter(|registration| registration.is_live(now))
+            .take(MAX_DISCOVER_RESULTS)
+            .cloned()
+            .collect();
+        DiscoverResponse {
+            namespace: namespace.clone(),
+            registrations,
+        }
+    }
+
+    /// Garbage-collects every registration whose TTL has lapsed by `now`.
+    pub fn remove_expired(&mut self, now: u64) {
+        for entries in self.records.values_mut() {
+            entries.retain(|registration| registration.is_live(now));
+        }
+        self.records.retain(|_, entries| !entries.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{id::FullId, rng, Prefix};
+
+    fn registration(expires_at: u64) -> Registration {
+        let full_id = FullId::gen(&mut rng::new());
+        let public_id = full_id.public_id().clone();
+        let addr = "127.0.0.1:12000".parse().unwrap();
+        let signature =
+            full_id.sign(&Registration::signed_bytes(&public_id, &addr, expires_at));
+        Registration {
+            public_id,
+            addr,
+            expires_at,
+            signature,
+        }
+    }
+
+    fn tampered_registration(expires_at: u64) -> Registration {
+        let mut registration = registration(expires_at);
+        registration.signature = vec![0; registration.signature.len()];
+        registration
+    }
+
+    #[test]
+    fn discover_omits_expired_registrations() {
+        let mut registry = RendezvousRegistry::new();
+        let namespace = Prefix::default();
+        let live = registration(100);
+        registry.register(namespace, live.clone(), 50);
+
+        let response = registry.discover(&namespace, 50);
+        assert_eq!(response.registrations, vec![live]);
+
+        let response = registry.discover(&namespace, 150);
+        assert!(response.registrations.is_empty());
+    }
+
+    #[test]
+    fn register_ignores_already_expired_records() {
+        let mut registry = RendezvousRegistry::new();
+        let namespace = Prefix::default();
+        registry.register(namespace, registration(10), 50);
+
+        assert!(registry.discover(&namespace, 50).registrations.is_empty());
+    }
+
+    #[test]
+    fn register_rejects_a_registration_with_an_invalid_signature() {
+        let mut registry = RendezvousRegistry::new();
+        let namespace = Prefix::default();
+        registry.register(namespace, tampered_registration(100), 0);
+
+        assert!(registry.discover(&namespace, 0).registrations.is_empty());
+    }
+
+    #[test]
+    fn discover_caps_results() {
+        let mut registry = RendezvousRegistry::new();
+        let namespace = Prefix::default();
+        for _ in 0..MAX_DISCOVER_RESULTS + 3 {
+            registry.register(namespace, registration(100), 0);
+        }
+
+        assert_eq!(
+            registry.discover(&namespace, 0).registrations.len(),
+            MAX_DISCOVER_RESULTS
+        );
+    }
+
+    #[test]
+    fn remove_expired_garbage_collects_lapsed_registrations() {
+        let mut registry = RendezvousRegistry::new();
+        let namespace = Prefix::default();
+        registry.register(namespace, registration(100), 50);
+        assert_eq!(registry.records.get(&namespace).unwrap().len(), 1);
+
+        registry.remove_expired(150);
+
+        assert!(registry.records.get(&namespace).is_none());
+        assert!(registry.discover(&namespace, 150).registrations.is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2018 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Connection admission control for `Comm`.
+//!
+//! [`ConnectionLimits`] bounds pending and established connections independently, and
+//! [`ConnectionCounters`] is the bookkeeping that enforces those bounds. `Comm` does not call
+//! [`ConnectionCounters`], so its dials and inbound connections are not limited by it.
+//!
+//! The crucial subtlety is in how the two kinds of counter are released. A *pending* count must
+//! be decremented on connection error or timeout, not only on a successful upgrade - otherwise a
+//! dial that never completes leaves a permanently-reserved slot. An *established* count must
+//! only ever be driven by the transport's own connect/accept/close callbacks
+//! ([`ConnectionCounters::on_established`]/[`ConnectionCounters::on_closed`]), never by a
+//! higher layer's acceptance decision, because that layer can still reject a peer after the
+//! connection already exists.
+
+use crate::id::PublicId;
+use std::{collections::HashMap, fmt};
+
+/// Configurable caps on concurrent connections handled by `Comm`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct ConnectionLimits {
+    /// Maximum number of inbound connections that may be mid-handshake at once.
+    pub max_pending_inbound: usize,
+    /// Maximum number of outbound connections that may be mid-dial at once.
+    pub max_pending_outbound: usize,
+    /// Maximum number of established connections allowed to a single peer.
+    pub max_established_per_peer: usize,
+    /// Maximum number of established connections allowed in total.
+    pub max_established_total: usize,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_pending_inbound: 128,
+            max_pending_outbound: 128,
+            max_established_per_peer: 2,
+            max_established_total: 1500,
+        }
+    }
+}
+
+/// Raised when a dial or inbound connection would exceed a configured [`ConnectionLimits`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    /// Too many inbound connections are already mid-handshake.
+    PendingInboundLimitReached,
+    /// Too many outbound connections are already mid-dial.
+    PendingOutboundLimitReached,
+    /// The peer already has `max_established_per_peer` established connections.
+    EstablishedPerPeerLimitReached,
+    /// The node already has `max_established_total` established connections.
+    EstablishedTotalLimitReached,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::PendingInboundLimitReached => write!(f, "too many pending inbound connections"),
+            Error::PendingOutboundLimitReached => {
+                write!(f, "too many pending outbound connections")
+            }
+            Error::EstablishedPerPeerLimitReached => {
+                write!(f, "too many established connections to this peer")
+            }
+            Error::EstablishedTotalLimitReached => {
+                write!(f, "too many established connections in total")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Tracks pending and established connection counts so `Comm` can enforce [`ConnectionLimits`].
+pub struct ConnectionCounters {
+    limits: ConnectionLimits,
+    pending_inbound: usize,
+    pending_outbound: usize,
+    established_per_peer: HashMap<PublicId, usize>,
+    established_total: usize,
+}
+
+impl ConnectionCounters {
+    /// Creates a counter set enforcing `limits`.
+    pub fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            limits,
+            pending_inbound: 0,
+            pending_outbound: 0,
+            established_per_peer: HashMap::new(),
+            established_total: 0,
+        }
+    }
+
+    /// Reserves a pending-inbound slot, refusing the connection early if the limit is reached.
+    pub fn reserve_pending_inbound(&mut self) -> Result<(), Error> {
+        if self.pending_inbound >= self.limits.max_pending_inbound {
+            return Err(Error::PendingInboundLimitReached);
+        }
+        self.pending_inbound += 1;
+        Ok(())
+    }
+
+    /// Reserves a pending-outbound slot, refusing the dial early if the limit is reached.
+    pub fn reserve_pending_outbound(&mut self) -> Result<(), Error> {
+        if self.pending_outbound >= self.limits.max_pending_outbound {
+            return Err(Error::PendingOutboundLimitReached);
+        }
+        self.pending_outbound += 1;
+        Ok(())
+    }
+
+    /// Releases a pending-inbound slot reserved by [`Self::reserve_pending_inbound`]. Must be
+    /// called exactly once per reservation, whether the connection goes on to succeed, error,
+    /// or time out.
+    pub fn release_pending_inbound(&mut self) {
+        self.pending_inbound = self.pending_inbound.saturating_sub(1);
+    }
+
+    /// Releases a pending-outbound slot reserved by [`Self::reserve_pending_outbound`]. Must be
+    /// called exactly once per reservation, whether the dial goes on to succeed, error, or time
+    /// out.
+    pub fn release_pending_outbound(&mut self) {
+        self.pending_outbound = self.pending_outbound.saturating_sub(1);
+    }
+
+    /// Records a newly-established connection to `peer`, called from the transport's own
+    /// connect/accept callback once the connection is fully upgraded. Independent of any
+    /// higher-layer acceptance decision, which can still reject `peer` afterwards without
+    /// affecting this count.
+    pub fn on_established(&mut self, peer: PublicId) -> Result<(), Error> {
+        if self.established_total >= self.limits.max_established_total {
+            return Err(Error::EstablishedTotalLimitReached);
+        }
+        let per_peer = self.established_per_peer.entry(peer).or_insert(0);
+        if *per_peer >= self.limits.max_established_per_peer {
+            return Err(Error::EstablishedPerPeerLimitReached);
+        }
+        *per_peer += 1;
+        self.established_total += 1;
+        Ok(())
+    }
+
+    /// Records that an established connection to `peer` has closed, called from the transport's
+    /// own close callback.
+    pub fn on_closed(&mut self, peer: &PublicId) {
+        if let Some(count) = self.established_per_peer.get_mut(peer) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                let _ = self.established_per_peer.remove(peer);
+            }
+            self.established_total = self.established_total.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ConnectionLimits {
+        ConnectionLimits {
+            max_pending_inbound: 1,
+            max_pending_outbound: 1,
+            max_established_per_peer: 1,
+            max_established_total: 1,
+        }
+    }
+
+    #[test]
+    fn pending_slot_is_released_on_error_not_just_on_success() {
+        let mut counters = ConnectionCounters::new(limits());
+        counters.reserve_pending_inbound().unwrap();
+        assert!(counters.reserve_pending_inbound().is_err());
+
+        // Simulates the connection failing rather than upgrading successfully.
+        counters.release_pending_inbound();
+        assert!(counters.reserve_pending_inbound().is_ok());
+    }
+
+    #[test]
+    fn established_total_limit_is_enforced() {
+        let mut counters = ConnectionCounters::new(limits());
+        counters.on_established(PublicId::random()).unwrap();
+        assert_eq!(
+            counters.on_established(PublicId::random()),
+            Err(Error::EstablishedTotalLimitReached)
+        );
+    }
+
+    #[test]
+    fn established_per_peer_limit_is_enforced() {
+        let mut limits = limits();
+        limits.max_established_total = 10;
+        let mut counters = ConnectionCounters::new(limits);
+        let peer = PublicId::random();
+        counters.on_established(peer).unwrap();
+        assert_eq!(
+            counters.on_established(peer),
+            Err(Error::EstablishedPerPeerLimitReached)
+        );
+    }
+
+    #[test]
+    fn closing_a_connection_frees_its_slot() {
+        let mut counters = ConnectionCounters::new(limits());
+        let peer = PublicId::random();
+        counters.on_established(peer).unwrap();
+        counters.on_closed(&peer);
+        assert!(counters.on_established(peer).is_ok());
+    }
+}